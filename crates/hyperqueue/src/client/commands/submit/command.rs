@@ -0,0 +1,51 @@
+use crate::client::commands::submit::defs::{JobDef, TaskConfigDef};
+use crate::common::error::HqError;
+use crate::JobTaskId;
+use std::path::Path;
+
+/// Reads and parses a job definition file (`hq submit --job-file <path>`), then expands
+/// it into the concrete, per-task configs to submit to the server. This is the real
+/// caller of `JobDef::build_tasks` (and, transitively, `ArrayDef::resolve_for_entry`)
+/// outside their own unit tests.
+pub fn tasks_from_job_file(path: &Path) -> crate::Result<Vec<(JobTaskId, TaskConfigDef)>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        HqError::GenericError(format!("failed to read job file {}: {e}", path.display()))
+    })?;
+    let job = JobDef::parse(&contents)?;
+    Ok(job.build_tasks())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tasks_from_job_file_substitutes_array_placeholders() {
+        let path = std::env::temp_dir().join(format!(
+            "hq_test_tasks_from_job_file_{:?}.toml",
+            std::thread::current().id()
+        ));
+        std::fs::write(
+            &path,
+            r#"
+        [array]
+        entries = ["foo", "bar"]
+        command = ["echo", "{{entry}}-{{id}}"]
+        "#,
+        )
+        .unwrap();
+
+        let tasks = tasks_from_job_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks[0].1.command,
+            vec!["echo".to_string(), "foo-0".to_string()]
+        );
+        assert_eq!(
+            tasks[1].1.command,
+            vec!["echo".to_string(), "bar-1".to_string()]
+        );
+    }
+}
@@ -4,7 +4,7 @@ use crate::common::arrayparser::parse_array;
 use crate::common::error::HqError;
 use crate::common::utils::time::parse_human_time;
 use crate::{JobTaskCount, JobTaskId};
-use bstr::BString;
+use bstr::{BString, ByteSlice};
 use serde::{Deserialize, Deserializer};
 use smallvec::SmallVec;
 use std::path::PathBuf;
@@ -20,7 +20,7 @@ pub enum IntOrString {
     String(String),
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, Deserialize)]
 pub enum PinMode {
     #[default]
     #[serde(rename = "none")]
@@ -86,7 +86,7 @@ where
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ResourceRequestDef {
     #[serde(default)]
@@ -111,7 +111,7 @@ impl ResourceRequestDef {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct TaskConfigDef {
     pub command: Vec<String>,
@@ -151,6 +151,65 @@ pub struct TaskConfigDef {
     pub stdin: Option<String>,
 }
 
+/// A partial `TaskConfigDef`, used to fill in fields that a `[[task]]` or `[array]` block
+/// didn't set itself. `command` is deliberately absent: it is always task-specific, so a
+/// `[defaults]` table that sets it is rejected by `deny_unknown_fields`.
+#[derive(Default, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TaskConfigDefaultsDef {
+    #[serde(default)]
+    pub env: Map<BString, BString>,
+
+    #[serde(default)]
+    pub request: SmallVec<[ResourceRequestDef; 1]>,
+
+    #[serde(default)]
+    pub cwd: Option<String>,
+
+    #[serde(default)]
+    pub pin: PinMode,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_human_duration_opt")]
+    pub time_limit: Option<Duration>,
+
+    #[serde(default)]
+    pub priority: Priority,
+
+    #[serde(default)]
+    pub crash_limit: u32,
+}
+
+impl TaskConfigDefaultsDef {
+    /// Fills in any field of `config` that was left at its default with the corresponding
+    /// default from this table. A task that explicitly sets a field to the same value as
+    /// the built-in default is indistinguishable from one that left it unset; this is an
+    /// accepted limitation of merging plain `#[serde(default)]` fields.
+    fn apply_to(&self, config: &mut TaskConfigDef) {
+        for (k, v) in &self.env {
+            config.env.entry(k.clone()).or_insert_with(|| v.clone());
+        }
+        if config.request.is_empty() {
+            config.request = self.request.clone();
+        }
+        if config.cwd.is_none() {
+            config.cwd = self.cwd.clone();
+        }
+        if matches!(config.pin, PinMode::None) {
+            config.pin = self.pin;
+        }
+        if config.time_limit.is_none() {
+            config.time_limit = self.time_limit;
+        }
+        if config.priority == Priority::default() {
+            config.priority = self.priority;
+        }
+        if config.crash_limit == 0 {
+            config.crash_limit = self.crash_limit;
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct TaskDef {
@@ -172,6 +231,19 @@ where
         .transpose()
 }
 
+/// Placeholder substituted with the array element's id.
+const ID_PLACEHOLDER: &str = "{{id}}";
+/// Placeholder substituted with the array element's entry (requires `entries` to be set).
+const ENTRY_PLACEHOLDER: &str = "{{entry}}";
+
+fn substitute_placeholders(template: &str, id: JobTaskId, entry: Option<&str>) -> String {
+    let result = template.replace(ID_PLACEHOLDER, &id.to_string());
+    match entry {
+        Some(entry) => result.replace(ENTRY_PLACEHOLDER, entry),
+        None => result,
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct ArrayDef {
@@ -186,6 +258,101 @@ pub struct ArrayDef {
     pub config: TaskConfigDef,
 }
 
+impl ArrayDef {
+    /// Every string in the config that placeholders are substituted into: `command`,
+    /// `env` values, `stdout`, `stderr` and `cwd`.
+    fn placeholder_templates(&self) -> impl Iterator<Item = &str> {
+        self.config
+            .command
+            .iter()
+            .map(String::as_str)
+            .chain(self.config.env.values().filter_map(|v| v.to_str().ok()))
+            .chain(self.config.stdout.as_deref())
+            .chain(self.config.stderr.as_deref())
+            .chain(self.config.cwd.as_deref())
+    }
+
+    /// Rejects `{{entry}}` placeholders when the array has no `entries` to fill them with.
+    fn validate_placeholders(&self) -> crate::Result<()> {
+        if self.entries.is_empty()
+            && self
+                .placeholder_templates()
+                .any(|s| s.contains(ENTRY_PLACEHOLDER))
+        {
+            return Err(HqError::DeserializationError(
+                "Array definition uses '{{entry}}' but no 'entries' were provided".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Materializes the `TaskConfigDef` for a single array element, substituting
+    /// `{{id}}` (always) and `{{entry}}` (when this element has one) into `command`,
+    /// `env` values, `stdout`, `stderr` and `cwd`.
+    pub fn resolve_for_entry(&self, id: JobTaskId, entry: Option<&str>) -> TaskConfigDef {
+        TaskConfigDef {
+            command: self
+                .config
+                .command
+                .iter()
+                .map(|arg| substitute_placeholders(arg, id, entry))
+                .collect(),
+            env: self
+                .config
+                .env
+                .iter()
+                .map(|(k, v)| {
+                    let v = match v.to_str() {
+                        Ok(v) => BString::from(substitute_placeholders(v, id, entry)),
+                        Err(_) => v.clone(),
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+            stdout: self
+                .config
+                .stdout
+                .as_deref()
+                .map(|s| substitute_placeholders(s, id, entry)),
+            stderr: self
+                .config
+                .stderr
+                .as_deref()
+                .map(|s| substitute_placeholders(s, id, entry)),
+            cwd: self
+                .config
+                .cwd
+                .as_deref()
+                .map(|s| substitute_placeholders(s, id, entry)),
+            request: self.config.request.clone(),
+            pin: self.config.pin,
+            task_dir: self.config.task_dir,
+            time_limit: self.config.time_limit,
+            priority: self.config.priority,
+            crash_limit: self.config.crash_limit,
+            stdin: self.config.stdin.clone(),
+        }
+    }
+
+    /// Expands this `[array]` block into the concrete, per-element task configs that get
+    /// submitted to the server, with `{{id}}`/`{{entry}}` placeholders already substituted
+    /// via [`ArrayDef::resolve_for_entry`].
+    fn build_tasks(&self) -> Vec<(JobTaskId, TaskConfigDef)> {
+        let ids: Vec<JobTaskId> = match &self.ids {
+            Some(ids) => ids.iter().map(JobTaskId::from).collect(),
+            None => (0..self.entries.len() as u32).map(JobTaskId::from).collect(),
+        };
+
+        ids.into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let entry = self.entries.get(i).map(String::as_str);
+                (id, self.resolve_for_entry(id, entry))
+            })
+            .collect()
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct JobDef {
@@ -201,9 +368,25 @@ pub struct JobDef {
     pub array: Option<ArrayDef>,
 
     pub stream_log: Option<PathBuf>,
+
+    #[serde(default)]
+    pub defaults: Option<TaskConfigDefaultsDef>,
 }
 
 impl JobDef {
+    /// Merges `[defaults]` into every task and array config that didn't override a field.
+    fn apply_defaults(&mut self) {
+        let Some(defaults) = &self.defaults else {
+            return;
+        };
+        for task in &mut self.tasks {
+            defaults.apply_to(&mut task.config);
+        }
+        if let Some(array) = &mut self.array {
+            defaults.apply_to(&mut array.config);
+        }
+    }
+
     pub fn validate(&self) -> crate::Result<()> {
         if self.tasks.is_empty() && self.array.is_none() {
             return Err(HqError::DeserializationError("No tasks defined".into()));
@@ -227,15 +410,35 @@ impl JobDef {
                     ));
                 }
             }
+            array.validate_placeholders()?;
         }
         Ok(())
     }
 
     pub fn parse(str: &str) -> crate::Result<JobDef> {
-        let jdef: JobDef = toml::from_str(str)?;
+        let mut jdef: JobDef = toml::from_str(str)?;
+        jdef.apply_defaults();
         jdef.validate()?;
         Ok(jdef)
     }
+
+    /// Expands this definition into the concrete, per-task configs that get submitted to
+    /// the server: `[[task]]` entries are used as-is, an `[array]` block is expanded one
+    /// element per id/entry (see [`ArrayDef::build_tasks`]). `validate` guarantees exactly
+    /// one of the two is present.
+    pub fn build_tasks(&self) -> Vec<(JobTaskId, TaskConfigDef)> {
+        if let Some(array) = &self.array {
+            return array.build_tasks();
+        }
+        self.tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| {
+                let id = task.id.unwrap_or_else(|| JobTaskId::from(i as u32));
+                (id, task.config.clone())
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -345,4 +548,96 @@ mod test {
             "55"
         );
     }
+
+    #[test]
+    fn test_defaults_fill_unset_fields() {
+        let r = JobDef::parse(
+            r#"
+        [defaults]
+        cwd = "/tmp"
+        env = {"ABC" = "abc"}
+
+        [[task]]
+        command = ["sleep", "1"]
+
+        [[task]]
+        command = ["sleep", "2"]
+        cwd = "/other"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(r.tasks[0].config.cwd.as_deref(), Some("/tmp"));
+        assert_eq!(
+            r.tasks[0]
+                .config
+                .env
+                .get(BString::from("ABC").as_bstr())
+                .unwrap(),
+            "abc"
+        );
+        assert_eq!(r.tasks[1].config.cwd.as_deref(), Some("/other"));
+    }
+
+    #[test]
+    fn test_defaults_reject_command() {
+        let r = JobDef::parse(
+            r#"
+        [defaults]
+        command = ["sleep", "1"]
+
+        [[task]]
+        command = ["sleep", "1"]
+        "#,
+        );
+        assert!(matches!(r, Err(HqError::DeserializationError(_))))
+    }
+
+    #[test]
+    fn test_array_entry_placeholder_substitution() {
+        let r = JobDef::parse(
+            r#"
+        [array]
+        entries = ["foo", "bar"]
+        command = ["echo", "{{entry}}-{{id}}"]
+        "#,
+        )
+        .unwrap();
+        let array = r.array.unwrap();
+        let config = array.resolve_for_entry(0.into(), Some("foo"));
+        assert_eq!(config.command, vec!["echo".to_string(), "foo-0".to_string()]);
+    }
+
+    #[test]
+    fn test_array_build_tasks_substitutes_placeholders() {
+        let r = JobDef::parse(
+            r#"
+        [array]
+        entries = ["foo", "bar"]
+        command = ["echo", "{{entry}}-{{id}}"]
+        "#,
+        )
+        .unwrap();
+        let tasks = r.build_tasks();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks[0].1.command,
+            vec!["echo".to_string(), "foo-0".to_string()]
+        );
+        assert_eq!(
+            tasks[1].1.command,
+            vec!["echo".to_string(), "bar-1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_array_entry_placeholder_without_entries_is_rejected() {
+        let r = JobDef::parse(
+            r#"
+        [array]
+        ids = "0-1"
+        command = ["echo", "{{entry}}"]
+        "#,
+        );
+        assert!(matches!(r, Err(HqError::DeserializationError(_))))
+    }
 }
@@ -0,0 +1,6 @@
+pub mod time_based_vec;
+pub mod time_interval;
+pub mod timelines;
+
+/// A point in time, as recorded by dashboard data structures.
+pub type Time = std::time::SystemTime;
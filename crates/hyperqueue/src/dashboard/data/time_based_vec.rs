@@ -0,0 +1,59 @@
+use crate::dashboard::data::time_interval::TimeRange;
+use crate::dashboard::data::Time;
+
+/// A value paired with the time it was recorded.
+#[derive(Clone, Debug)]
+pub struct ItemWithTime<T> {
+    pub time: Time,
+    pub item: T,
+}
+
+/// An append-only, time-ordered log of samples. Callers are expected to always `push`
+/// with a non-decreasing `time`, which holds for monitoring events (they are replayed in
+/// order) and lets lookups use a binary search instead of a linear scan.
+#[derive(Clone, Debug)]
+pub struct TimeBasedVec<T> {
+    items: Vec<ItemWithTime<T>>,
+}
+
+impl<T> Default for TimeBasedVec<T> {
+    fn default() -> Self {
+        TimeBasedVec { items: Vec::new() }
+    }
+}
+
+impl<T> TimeBasedVec<T> {
+    pub fn push(&mut self, time: Time, item: T) {
+        self.items.push(ItemWithTime { time, item });
+    }
+
+    pub fn get_most_recent_at(&self, time: Time) -> Option<&ItemWithTime<T>> {
+        let index = self.items.partition_point(|entry| entry.time <= time);
+        self.items[..index].last()
+    }
+
+    /// The most recently pushed item, if any, mutable in place so a caller can amend it
+    /// (e.g. to merge further data into the last bucket of a compacted timeline) instead
+    /// of pushing a disjoint new entry.
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.items.last_mut().map(|entry| &mut entry.item)
+    }
+
+    pub fn get_time_range(&self, range: TimeRange) -> &[ItemWithTime<T>] {
+        let start = self
+            .items
+            .partition_point(|entry| range.starts_after(entry.time));
+        let end = start
+            + self.items[start..]
+                .partition_point(|entry| range.contains(entry.time));
+        &self.items[start..end]
+    }
+
+    /// Removes every sample older than `cutoff` and returns them (oldest first), so the
+    /// caller can reduce them into coarser buckets before they're discarded. Samples at
+    /// exactly `cutoff` are kept, matching `get_time_range`'s inclusive lower bound.
+    pub fn drain_older_than(&mut self, cutoff: Time) -> Vec<ItemWithTime<T>> {
+        let split_at = self.items.partition_point(|entry| entry.time < cutoff);
+        self.items.drain(0..split_at).collect()
+    }
+}
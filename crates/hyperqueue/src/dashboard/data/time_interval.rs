@@ -0,0 +1,38 @@
+use crate::dashboard::data::Time;
+
+/// An inclusive range of points in time, used to query a window of dashboard history.
+/// `None` on either end means "unbounded".
+#[derive(Clone, Copy, Debug)]
+pub struct TimeRange {
+    start: Option<Time>,
+    end: Option<Time>,
+}
+
+impl TimeRange {
+    pub fn new(start: Option<Time>, end: Option<Time>) -> Self {
+        TimeRange { start, end }
+    }
+
+    pub fn since(start: Time) -> Self {
+        TimeRange {
+            start: Some(start),
+            end: None,
+        }
+    }
+
+    pub fn until(end: Time) -> Self {
+        TimeRange {
+            start: None,
+            end: Some(end),
+        }
+    }
+
+    pub fn contains(&self, time: Time) -> bool {
+        self.start.map_or(true, |start| time >= start) && self.end.map_or(true, |end| time <= end)
+    }
+
+    /// Whether `time` lies before the start of this range.
+    pub fn starts_after(&self, time: Time) -> bool {
+        self.start.map_or(false, |start| time < start)
+    }
+}
@@ -4,22 +4,228 @@ use crate::dashboard::data::Time;
 use crate::server::event::events::MonitoringEventPayload;
 use crate::server::event::MonitoringEvent;
 use crate::WorkerId;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tako::gateway::LostWorkerReason;
 use tako::worker::{WorkerConfiguration, WorkerOverview};
 use tako::Map;
 
+/// A worker is considered `Unreachable` once its most recent overview is older than
+/// this multiple of the configured overview interval.
+const OVERVIEW_STALENESS_FACTOR: u32 = 2;
+
+/// Coarse classification of a worker's activity at a single point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorkerState {
+    /// Connected, with a recent overview reporting at least one running task.
+    Active,
+    /// Connected, with a recent overview reporting no running tasks.
+    Idle,
+    /// Disconnected as of the queried time.
+    Dead,
+    /// Still connected, but hasn't sent an overview recently enough to trust.
+    Unreachable,
+}
+
 #[derive(Clone)]
 pub struct WorkerDisconnectInfo {
     pub reason: LostWorkerReason,
     pub time: Time,
 }
 
+/// Only the recent `worker_overviews` are kept at full resolution; samples older than
+/// `full_resolution_window` are compacted into `bucket_width`-sized buckets to keep
+/// long-running clusters from growing the timeline without bound.
+struct RetentionPolicy {
+    full_resolution_window: Duration,
+    bucket_width: Duration,
+    compaction_period: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            full_resolution_window: Duration::from_secs(15 * 60),
+            bucket_width: Duration::from_secs(60),
+            compaction_period: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Min/max/mean reduction of the numeric fields of `WorkerOverview` collected within a
+/// single, epoch-aligned time bucket. The discrete `running_tasks` field is kept as the
+/// last value observed in the bucket, matching how a live dashboard reads it.
+#[derive(Clone, Debug)]
+pub struct AggregatedOverview {
+    pub bucket_start: Time,
+    pub bucket_end: Time,
+    pub cpu_usage_min: f32,
+    pub cpu_usage_max: f32,
+    pub cpu_usage_mean: f32,
+    pub memory_usage_min: u64,
+    pub memory_usage_max: u64,
+    pub memory_usage_mean: u64,
+    pub running_tasks: usize,
+    /// How many raw samples were reduced into this bucket so far, and the exact sums
+    /// behind `cpu_usage_mean`/`memory_usage_mean`, kept so a later `compact` call
+    /// covering the same `bucket_start` can resume accumulation from the true totals
+    /// instead of reconstructing them from the already-rounded means (which would lose
+    /// precision a little more on every merge).
+    pub sample_count: u64,
+    pub cpu_usage_sum: f32,
+    pub memory_usage_sum: u64,
+}
+
+/// The samples contributed so far towards an in-progress bucket, before they have been
+/// reduced into an `AggregatedOverview`.
+struct BucketAccumulator {
+    bucket_start: Time,
+    count: u64,
+    cpu_usage_min: f32,
+    cpu_usage_max: f32,
+    cpu_usage_sum: f32,
+    memory_usage_min: u64,
+    memory_usage_max: u64,
+    memory_usage_sum: u64,
+    last_running_tasks: usize,
+}
+
+impl BucketAccumulator {
+    fn new(bucket_start: Time, overview: &WorkerOverview) -> Self {
+        let cpu_usage = cpu_usage_of(overview);
+        let memory_usage = memory_usage_of(overview);
+        BucketAccumulator {
+            bucket_start,
+            count: 1,
+            cpu_usage_min: cpu_usage,
+            cpu_usage_max: cpu_usage,
+            cpu_usage_sum: cpu_usage,
+            memory_usage_min: memory_usage,
+            memory_usage_max: memory_usage,
+            memory_usage_sum: memory_usage,
+            last_running_tasks: overview.running_tasks.len(),
+        }
+    }
+
+    /// Resumes accumulation from a bucket a previous `compact` call already produced for
+    /// this `bucket_start`, so a later call covering the rest of the same bucket merges
+    /// into it instead of starting a disjoint one.
+    fn from_aggregated(existing: &AggregatedOverview) -> Self {
+        BucketAccumulator {
+            bucket_start: existing.bucket_start,
+            count: existing.sample_count,
+            cpu_usage_min: existing.cpu_usage_min,
+            cpu_usage_max: existing.cpu_usage_max,
+            cpu_usage_sum: existing.cpu_usage_sum,
+            memory_usage_min: existing.memory_usage_min,
+            memory_usage_max: existing.memory_usage_max,
+            memory_usage_sum: existing.memory_usage_sum,
+            last_running_tasks: existing.running_tasks,
+        }
+    }
+
+    fn add(&mut self, overview: &WorkerOverview) {
+        let cpu_usage = cpu_usage_of(overview);
+        let memory_usage = memory_usage_of(overview);
+        self.count += 1;
+        self.cpu_usage_min = self.cpu_usage_min.min(cpu_usage);
+        self.cpu_usage_max = self.cpu_usage_max.max(cpu_usage);
+        self.cpu_usage_sum += cpu_usage;
+        self.memory_usage_min = self.memory_usage_min.min(memory_usage);
+        self.memory_usage_max = self.memory_usage_max.max(memory_usage);
+        self.memory_usage_sum += memory_usage;
+        self.last_running_tasks = overview.running_tasks.len();
+    }
+
+    /// Merges `other`'s samples into `self`, as if they had all been `add`ed in order.
+    fn merge(&mut self, other: &BucketAccumulator) {
+        self.count += other.count;
+        self.cpu_usage_min = self.cpu_usage_min.min(other.cpu_usage_min);
+        self.cpu_usage_max = self.cpu_usage_max.max(other.cpu_usage_max);
+        self.cpu_usage_sum += other.cpu_usage_sum;
+        self.memory_usage_min = self.memory_usage_min.min(other.memory_usage_min);
+        self.memory_usage_max = self.memory_usage_max.max(other.memory_usage_max);
+        self.memory_usage_sum += other.memory_usage_sum;
+        self.last_running_tasks = other.last_running_tasks;
+    }
+
+    fn finish(self, bucket_width: Duration) -> AggregatedOverview {
+        AggregatedOverview {
+            bucket_start: self.bucket_start,
+            bucket_end: self.bucket_start + bucket_width,
+            cpu_usage_min: self.cpu_usage_min,
+            cpu_usage_max: self.cpu_usage_max,
+            cpu_usage_mean: self.cpu_usage_sum / self.count as f32,
+            memory_usage_min: self.memory_usage_min,
+            memory_usage_max: self.memory_usage_max,
+            memory_usage_mean: self.memory_usage_sum / self.count,
+            running_tasks: self.last_running_tasks,
+            sample_count: self.count,
+            cpu_usage_sum: self.cpu_usage_sum,
+            memory_usage_sum: self.memory_usage_sum,
+        }
+    }
+}
+
+/// A single point read back from a worker's overview history: either a full-resolution
+/// `WorkerOverview` from the recent window, or an `AggregatedOverview` bucket compacted
+/// from older samples.
+#[derive(Clone, Debug)]
+pub enum OverviewSample {
+    Full(ItemWithTime<WorkerOverview>),
+    Aggregated(ItemWithTime<AggregatedOverview>),
+}
+
+impl OverviewSample {
+    pub fn time(&self) -> Time {
+        match self {
+            OverviewSample::Full(item) => item.time,
+            OverviewSample::Aggregated(item) => item.time,
+        }
+    }
+}
+
+fn cpu_usage_of(overview: &WorkerOverview) -> f32 {
+    overview
+        .hw_state
+        .as_ref()
+        .map(|hw| {
+            let samples = &hw.worker_cpu_usage.cpu_per_core_percent_usage;
+            if samples.is_empty() {
+                0.0
+            } else {
+                samples.iter().sum::<f32>() / samples.len() as f32
+            }
+        })
+        .unwrap_or(0.0)
+}
+
+fn memory_usage_of(overview: &WorkerOverview) -> u64 {
+    overview
+        .hw_state
+        .as_ref()
+        .map(|hw| hw.worker_memory_usage.total.saturating_sub(hw.worker_memory_usage.free))
+        .unwrap_or(0)
+}
+
+/// Aligns `time` down to the start of the `bucket_width` bucket that contains it, relative
+/// to the Unix epoch. Aligning to the epoch (rather than to the first observed sample)
+/// keeps bucket boundaries deterministic, so repeated compaction passes are idempotent.
+fn align_to_bucket(time: Time, bucket_width: Duration) -> Time {
+    let since_epoch = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let bucket_width_secs = bucket_width.as_secs().max(1);
+    let bucket_index = since_epoch.as_secs() / bucket_width_secs;
+    std::time::UNIX_EPOCH + Duration::from_secs(bucket_index * bucket_width_secs)
+}
+
 pub struct WorkerRecord {
     id: WorkerId,
     connection_time: SystemTime,
     worker_config: WorkerConfiguration,
     worker_overviews: TimeBasedVec<WorkerOverview>,
+    aggregated_overviews: TimeBasedVec<AggregatedOverview>,
+    last_compaction: Option<SystemTime>,
 
     disconnect_info: Option<WorkerDisconnectInfo>,
 }
@@ -31,17 +237,80 @@ impl WorkerRecord {
             time: loss_time,
         });
     }
+
+    /// Moves every full-resolution sample older than `now - policy.full_resolution_window`
+    /// out of `worker_overviews`, reducing each one into the `AggregatedOverview` bucket it
+    /// falls into. Bucket boundaries are epoch-aligned, so which bucket a sample falls
+    /// into is always the same; `flush_bucket` additionally merges into the existing last
+    /// bucket rather than appending a new one when this call's first bucket continues a
+    /// bucket a previous call already produced, so a single bucket is never split across
+    /// two disjoint `AggregatedOverview` entries just because compaction happened to fire
+    /// mid-bucket.
+    fn compact(&mut self, now: SystemTime, policy: &RetentionPolicy) {
+        let cutoff = now
+            .checked_sub(policy.full_resolution_window)
+            .unwrap_or(now);
+        let stale = self.worker_overviews.drain_older_than(cutoff);
+        if stale.is_empty() {
+            self.last_compaction = Some(now);
+            return;
+        }
+
+        let mut current: Option<BucketAccumulator> = None;
+        for sample in stale {
+            let bucket_start = align_to_bucket(sample.time, policy.bucket_width);
+            match &mut current {
+                Some(acc) if acc.bucket_start == bucket_start => acc.add(&sample.item),
+                _ => {
+                    if let Some(acc) = current.take() {
+                        self.flush_bucket(acc, policy.bucket_width);
+                    }
+                    current = Some(BucketAccumulator::new(bucket_start, &sample.item));
+                }
+            }
+        }
+        if let Some(acc) = current {
+            self.flush_bucket(acc, policy.bucket_width);
+        }
+        self.last_compaction = Some(now);
+    }
+
+    /// Pushes `acc` as a new aggregated bucket, or merges it into the existing last
+    /// bucket when a previous `compact` call already produced one for the same
+    /// `bucket_start`. Compaction fires whenever an event's timestamp has drifted
+    /// `compaction_period` past the last run, not on epoch-aligned ticks, so a single
+    /// `bucket_width` bucket routinely has its samples split across two consecutive
+    /// calls; without merging, that produced two disjoint buckets for the same time range.
+    fn flush_bucket(&mut self, acc: BucketAccumulator, bucket_width: Duration) {
+        if let Some(last) = self.aggregated_overviews.last_mut() {
+            if last.bucket_start == acc.bucket_start {
+                let mut merged = BucketAccumulator::from_aggregated(last);
+                merged.merge(&acc);
+                *last = merged.finish(bucket_width);
+                return;
+            }
+        }
+        let aggregated = acc.finish(bucket_width);
+        self.aggregated_overviews
+            .push(aggregated.bucket_start, aggregated);
+    }
 }
 
 /// Stores information about the workers at different times
 #[derive(Default)]
 pub struct WorkerTimeline {
     workers: Map<WorkerId, WorkerRecord>,
+    /// Shared by `handle_new_events`/`compact` and `get_worker_state_at`, so both agree on
+    /// exactly one retention window instead of each constructing their own `Default` that
+    /// only coincidentally matches.
+    retention_policy: RetentionPolicy,
 }
 
 impl WorkerTimeline {
-    /// Assumes that `events` are sorted by time.
+    /// Assumes that `events` are sorted by time. Periodically triggers compaction of
+    /// worker overview history so memory use stays bounded on long-running clusters.
     pub fn handle_new_events(&mut self, events: &[MonitoringEvent]) {
+        let policy = &self.retention_policy;
         for event in events {
             match &event.payload {
                 MonitoringEventPayload::WorkerConnected(id, info) => {
@@ -52,6 +321,8 @@ impl WorkerTimeline {
                             connection_time: event.time,
                             worker_config: *info.clone(),
                             worker_overviews: Default::default(),
+                            aggregated_overviews: Default::default(),
+                            last_compaction: None,
                             disconnect_info: None,
                         },
                     );
@@ -68,6 +339,20 @@ impl WorkerTimeline {
                 }
                 _ => {}
             }
+
+            for worker in self.workers.values_mut() {
+                let due = match worker.last_compaction {
+                    Some(last) => event
+                        .time
+                        .duration_since(last)
+                        .unwrap_or_default()
+                        >= policy.compaction_period,
+                    None => true,
+                };
+                if due {
+                    worker.compact(event.time, policy);
+                }
+            }
         }
     }
 
@@ -114,13 +399,97 @@ impl WorkerTimeline {
             .and_then(|worker| worker.worker_overviews.get_most_recent_at(time))
     }
 
+    /// Returns the samples within `range`, transparently mixing full-resolution samples
+    /// from the recent window with compacted `AggregatedOverview` buckets for any part of
+    /// `range` that falls outside the retention window. Samples are ordered by time.
     pub fn get_worker_overviews_at(
         &self,
         worker_id: WorkerId,
         range: TimeRange,
-    ) -> Option<&[ItemWithTime<WorkerOverview>]> {
-        self.workers
-            .get(&worker_id)
-            .map(|worker| worker.worker_overviews.get_time_range(range))
+    ) -> Option<Vec<OverviewSample>> {
+        let worker = self.workers.get(&worker_id)?;
+        let mut samples: Vec<OverviewSample> = worker
+            .aggregated_overviews
+            .get_time_range(range)
+            .iter()
+            .cloned()
+            .map(OverviewSample::Aggregated)
+            .collect();
+        samples.extend(
+            worker
+                .worker_overviews
+                .get_time_range(range)
+                .iter()
+                .cloned()
+                .map(OverviewSample::Full),
+        );
+        samples.sort_by_key(OverviewSample::time);
+        Some(samples)
+    }
+
+    /// Classifies a worker's activity at `time`, given how often workers are expected
+    /// to send overviews (`overview_interval`).
+    ///
+    /// `time` may fall outside the full-resolution retention window, in which case the
+    /// matching sample has already been compacted into an `AggregatedOverview` bucket;
+    /// this is consulted as a fallback, but *only* for such genuinely historical queries
+    /// (`time` itself older than `full_resolution_window`). A query near the present
+    /// whose live sample happens to be stale must still resolve to `Unreachable`: the
+    /// worker's older, healthy history is still sitting in `aggregated_overviews` and
+    /// would otherwise mask the very condition this state exists to detect.
+    pub fn get_worker_state_at(
+        &self,
+        worker_id: WorkerId,
+        time: SystemTime,
+        overview_interval: Duration,
+    ) -> Option<WorkerState> {
+        let worker = self.workers.get(&worker_id)?;
+        if worker.connection_time > time {
+            return None;
+        }
+        if let Some(info) = &worker.disconnect_info {
+            if info.time <= time {
+                return Some(WorkerState::Dead);
+            }
+        }
+
+        let staleness_threshold = overview_interval * OVERVIEW_STALENESS_FACTOR;
+        if let Some(overview) = worker.worker_overviews.get_most_recent_at(time) {
+            if time.duration_since(overview.time).unwrap_or_default() <= staleness_threshold {
+                return Some(if overview.item.running_tasks.is_empty() {
+                    WorkerState::Idle
+                } else {
+                    WorkerState::Active
+                });
+            }
+        }
+
+        let full_resolution_window = self.retention_policy.full_resolution_window;
+        let is_historical_query = SystemTime::now()
+            .duration_since(time)
+            .unwrap_or_default()
+            >= full_resolution_window;
+        if is_historical_query {
+            if let Some(bucket) = worker.aggregated_overviews.get_most_recent_at(time) {
+                return Some(if bucket.item.running_tasks == 0 {
+                    WorkerState::Idle
+                } else {
+                    WorkerState::Active
+                });
+            }
+        }
+
+        Some(WorkerState::Unreachable)
+    }
+
+    /// Aggregates the activity of every known worker at `time` into a cluster health summary.
+    pub fn count_states_at(&self, time: SystemTime, overview_interval: Duration) -> Map<WorkerState, usize> {
+        let mut counts: Map<WorkerState, usize> = Map::default();
+        for worker_id in self.get_worker_ids() {
+            if let Some(state) = self.get_worker_state_at(worker_id, time, overview_interval) {
+                *counts.entry(state).or_insert(0) += 1;
+            }
+        }
+        counts
     }
 }
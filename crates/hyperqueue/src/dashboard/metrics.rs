@@ -0,0 +1,182 @@
+use crate::dashboard::data::timelines::worker_timeline::WorkerTimeline;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write as _};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tako::gateway::LostWorkerReason;
+
+/// Runs an HTTP `/metrics` endpoint, sourced from `timeline`, until the listener fails.
+/// Intended to be spawned on its own thread alongside the rest of the server so scrapers
+/// don't have to go through the interactive dashboard.
+pub fn serve_metrics(
+    addr: impl ToSocketAddrs,
+    timeline: Arc<Mutex<WorkerTimeline>>,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let body = render_metrics(&timeline.lock().unwrap(), SystemTime::now());
+        if let Err(error) = respond(stream, &body) {
+            log::warn!("Failed to serve a /metrics scrape: {error}");
+        }
+    }
+    Ok(())
+}
+
+/// Reads (and discards) the HTTP request line and headers, then writes back `body` as an
+/// OpenMetrics text response. Every request is answered the same way; there is only one
+/// route to serve.
+fn respond(stream: TcpStream, body: &str) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: application/openmetrics-text; version=1.0.0; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n\
+         {body}",
+        body.len()
+    )
+}
+
+/// Renders the live state of `timeline` as an OpenMetrics/Prometheus text exposition,
+/// so a cluster can be scraped and alerted on without the interactive dashboard.
+///
+/// Workers that haven't sent an overview yet are skipped; everything else is reported as
+/// of the most recent overview each worker has sent by `now`.
+pub fn render_metrics(timeline: &WorkerTimeline, now: SystemTime) -> String {
+    let mut out = String::new();
+
+    write_worker_gauges(&mut out, timeline, now);
+    write_connection_counters(&mut out, timeline, now);
+
+    out
+}
+
+fn write_worker_gauges(out: &mut String, timeline: &WorkerTimeline, now: SystemTime) {
+    writeln!(
+        out,
+        "# HELP hq_worker_cpu_usage_percent Mean CPU utilization reported by the worker's most recent overview."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE hq_worker_cpu_usage_percent gauge").unwrap();
+    writeln!(
+        out,
+        "# HELP hq_worker_memory_usage_bytes Memory in use, reported by the worker's most recent overview."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE hq_worker_memory_usage_bytes gauge").unwrap();
+    writeln!(
+        out,
+        "# HELP hq_worker_running_tasks Number of tasks the worker was running in its most recent overview."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE hq_worker_running_tasks gauge").unwrap();
+    writeln!(
+        out,
+        "# HELP hq_worker_generic_resource_total Total units of a generic resource the worker has available."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE hq_worker_generic_resource_total gauge").unwrap();
+
+    for worker_id in timeline.get_worker_ids() {
+        let Some(overview) = timeline.get_worker_overview_at(worker_id, now) else {
+            continue;
+        };
+
+        if let Some(hw_state) = &overview.item.hw_state {
+            let samples = &hw_state.worker_cpu_usage.cpu_per_core_percent_usage;
+            let cpu_usage = if samples.is_empty() {
+                0.0
+            } else {
+                samples.iter().sum::<f32>() / samples.len() as f32
+            };
+            writeln!(
+                out,
+                "hq_worker_cpu_usage_percent{{worker=\"{worker_id}\"}} {cpu_usage}"
+            )
+            .unwrap();
+
+            let memory_usage = hw_state
+                .worker_memory_usage
+                .total
+                .saturating_sub(hw_state.worker_memory_usage.free);
+            writeln!(
+                out,
+                "hq_worker_memory_usage_bytes{{worker=\"{worker_id}\"}} {memory_usage}"
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            out,
+            "hq_worker_running_tasks{{worker=\"{worker_id}\"}} {}",
+            overview.item.running_tasks.len()
+        )
+        .unwrap();
+
+        if let Some(config) = timeline.get_worker_config_for(worker_id) {
+            for resource in &config.resources.generic {
+                writeln!(
+                    out,
+                    "hq_worker_generic_resource_total{{worker=\"{worker_id}\",resource=\"{}\"}} {}",
+                    resource.name,
+                    resource.kind.size()
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+fn write_connection_counters(out: &mut String, timeline: &WorkerTimeline, now: SystemTime) {
+    writeln!(
+        out,
+        "# HELP hq_workers_connected_total Number of workers currently connected to the server."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE hq_workers_connected_total gauge").unwrap();
+    writeln!(
+        out,
+        "hq_workers_connected_total {}",
+        timeline.get_connected_worker_ids_at(now).count()
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        "# HELP hq_workers_lost_total Number of workers that have disconnected, by reason."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE hq_workers_lost_total counter").unwrap();
+    let mut lost_by_reason: tako::Map<String, u64> = tako::Map::default();
+    for worker_id in timeline.get_worker_ids() {
+        if let Some(info) = timeline.get_worker_disconnect_info(worker_id) {
+            *lost_by_reason
+                .entry(lost_worker_reason_label(&info.reason).to_string())
+                .or_insert(0) += 1;
+        }
+    }
+    for (reason, count) in lost_by_reason {
+        writeln!(out, "hq_workers_lost_total{{reason=\"{reason}\"}} {count}").unwrap();
+    }
+}
+
+fn lost_worker_reason_label(reason: &LostWorkerReason) -> &'static str {
+    match reason {
+        LostWorkerReason::HeartbeatLost => "heartbeat_lost",
+        LostWorkerReason::IdleTimeout => "idle_timeout",
+        LostWorkerReason::Stopped => "stopped",
+        LostWorkerReason::ConnectionLost => "connection_lost",
+    }
+}
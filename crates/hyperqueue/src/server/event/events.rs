@@ -0,0 +1,111 @@
+use crate::common::error::HqError;
+use crate::server::event::migrate::Migrate;
+use crate::WorkerId;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use tako::gateway::LostWorkerReason;
+use tako::worker::{WorkerConfiguration, WorkerOverview};
+
+/// V1 on-disk shape of `MonitoringEventPayload`, kept only so `migrate` can still decode
+/// journals written before `WorkerHeartbeat` gained a sequence number.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum MonitoringEventPayloadV1 {
+    WorkerConnected(WorkerId, Box<WorkerConfiguration>),
+    WorkerLost(WorkerId, LostWorkerReason),
+    WorkerOverviewReceived(WorkerOverview),
+    WorkerHeartbeat(WorkerId),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MonitoringEventPayload {
+    WorkerConnected(WorkerId, Box<WorkerConfiguration>),
+    WorkerLost(WorkerId, LostWorkerReason),
+    WorkerOverviewReceived(WorkerOverview),
+    /// The worker that sent the heartbeat, and a sequence number it assigns, used to
+    /// detect dropped heartbeats on replay. Added in V2; V1 journals have no such
+    /// number, so `migrate` fills it in as `0`.
+    WorkerHeartbeat(WorkerId, u64),
+}
+
+impl Migrate for MonitoringEventPayload {
+    const VERSION: u16 = 2;
+
+    fn migrate(bytes: &[u8], from_version: u16) -> crate::Result<Self> {
+        match from_version {
+            2 => bincode::deserialize(bytes).map_err(|e| {
+                HqError::GenericError(format!("failed to deserialize monitoring event: {e}"))
+            }),
+            1 => {
+                let old: MonitoringEventPayloadV1 = bincode::deserialize(bytes).map_err(|e| {
+                    HqError::GenericError(format!("failed to deserialize v1 monitoring event: {e}"))
+                })?;
+                Ok(match old {
+                    MonitoringEventPayloadV1::WorkerConnected(id, config) => {
+                        MonitoringEventPayload::WorkerConnected(id, config)
+                    }
+                    MonitoringEventPayloadV1::WorkerLost(id, reason) => {
+                        MonitoringEventPayload::WorkerLost(id, reason)
+                    }
+                    MonitoringEventPayloadV1::WorkerOverviewReceived(overview) => {
+                        MonitoringEventPayload::WorkerOverviewReceived(overview)
+                    }
+                    MonitoringEventPayloadV1::WorkerHeartbeat(id) => {
+                        MonitoringEventPayload::WorkerHeartbeat(id, 0)
+                    }
+                })
+            }
+            _ => Err(HqError::GenericError(format!(
+                "no migration path from monitoring event version {from_version} to {}",
+                Self::VERSION
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MonitoringEvent {
+    pub time: SystemTime,
+    pub payload: MonitoringEventPayload,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::event::migrate::{decode_versioned, encode_versioned};
+
+    #[test]
+    fn test_migrates_v1_heartbeat_to_v2() {
+        let id: WorkerId = 0.into();
+        let v1_bytes = bincode::serialize(&MonitoringEventPayloadV1::WorkerHeartbeat(id)).unwrap();
+        let mut record = 1u16.to_le_bytes().to_vec();
+        record.extend(v1_bytes);
+
+        let decoded: MonitoringEventPayload = decode_versioned(&record).unwrap();
+        assert!(matches!(
+            decoded,
+            MonitoringEventPayload::WorkerHeartbeat(decoded_id, 0) if decoded_id == id
+        ));
+    }
+
+    #[test]
+    fn test_roundtrips_v2_heartbeat() {
+        let id: WorkerId = 0.into();
+        let payload = MonitoringEventPayload::WorkerHeartbeat(id, 7);
+
+        let record = encode_versioned(&payload).unwrap();
+        let decoded: MonitoringEventPayload = decode_versioned(&record).unwrap();
+
+        assert!(matches!(
+            decoded,
+            MonitoringEventPayload::WorkerHeartbeat(decoded_id, 7) if decoded_id == id
+        ));
+    }
+
+    #[test]
+    fn test_rejects_future_version() {
+        let mut record = 3u16.to_le_bytes().to_vec();
+        record.extend(vec![0u8; 4]);
+        let result: crate::Result<MonitoringEventPayload> = decode_versioned(&record);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::common::error::HqError;
+use crate::server::event::events::MonitoringEvent;
+use crate::server::event::migrate::{decode_versioned, encode_versioned};
+use std::io::{Read, Write};
+
+/// Appends one monitoring event to a journal stream: its timestamp, followed by its
+/// payload encoded with a version tag (see `migrate`) so a journal written by an older
+/// HyperQueue can still be replayed after the payload's on-disk shape changes.
+pub fn write_event(writer: &mut impl Write, event: &MonitoringEvent) -> crate::Result<()> {
+    let time = bincode::serialize(&event.time)
+        .map_err(|e| HqError::GenericError(format!("failed to serialize event timestamp: {e}")))?;
+    write_framed(writer, &time)?;
+    write_framed(writer, &encode_versioned(&event.payload)?)
+}
+
+/// Reads back one event written by `write_event`, running its payload through the
+/// migration chain needed to reach the current version.
+pub fn read_event(reader: &mut impl Read) -> crate::Result<MonitoringEvent> {
+    let time_bytes = read_framed(reader)?;
+    let time = bincode::deserialize(&time_bytes)
+        .map_err(|e| HqError::GenericError(format!("failed to deserialize event timestamp: {e}")))?;
+    let payload_bytes = read_framed(reader)?;
+    let payload = decode_versioned(&payload_bytes)?;
+    Ok(MonitoringEvent { time, payload })
+}
+
+fn write_framed(writer: &mut impl Write, bytes: &[u8]) -> crate::Result<()> {
+    writer
+        .write_all(&(bytes.len() as u32).to_le_bytes())
+        .and_then(|_| writer.write_all(bytes))
+        .map_err(|e| HqError::GenericError(format!("failed to write journal record: {e}")))
+}
+
+fn read_framed(reader: &mut impl Read) -> crate::Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    reader
+        .read_exact(&mut len_bytes)
+        .map_err(|e| HqError::GenericError(format!("failed to read journal record length: {e}")))?;
+    let mut bytes = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|e| HqError::GenericError(format!("failed to read journal record: {e}")))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::event::events::MonitoringEventPayload;
+    use crate::WorkerId;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_roundtrip_event() {
+        let id: WorkerId = 0.into();
+        let event = MonitoringEvent {
+            time: SystemTime::now(),
+            payload: MonitoringEventPayload::WorkerHeartbeat(id, 3),
+        };
+
+        let mut buffer = Vec::new();
+        write_event(&mut buffer, &event).unwrap();
+        let decoded = read_event(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(decoded.time, event.time);
+        assert!(
+            matches!(decoded.payload, MonitoringEventPayload::WorkerHeartbeat(decoded_id, 3) if decoded_id == id)
+        );
+    }
+
+    #[test]
+    fn test_rejects_truncated_record() {
+        let mut buffer = vec![1, 0, 0, 0];
+        assert!(read_event(&mut buffer.as_slice()).is_err());
+    }
+}
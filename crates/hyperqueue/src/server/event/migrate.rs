@@ -0,0 +1,68 @@
+use crate::common::error::HqError;
+use serde::Serialize;
+
+/// A type that can be deserialized from any earlier on-disk encoding of itself, not just
+/// the current one.
+///
+/// The dashboard and `hq event-log` reconstruct all state by replaying `MonitoringEvent`s
+/// from a journal file, so a field added or renamed in `MonitoringEventPayload` (or in
+/// anything it carries, such as `WorkerConfiguration`/`WorkerOverview`) must not break
+/// replay of journals written by older HyperQueue versions. Implementors describe how to
+/// upgrade every encoding older than `VERSION` into the current one, field-by-field.
+pub trait Migrate: Sized {
+    /// Bumped whenever the serialized shape of `Self` changes.
+    const VERSION: u16;
+
+    /// Upgrades `bytes`, encoded by a version `from_version <= Self::VERSION`, into the
+    /// current version of `Self`. `from_version == Self::VERSION` is just a plain decode.
+    fn migrate(bytes: &[u8], from_version: u16) -> crate::Result<Self>;
+}
+
+/// Decodes a record written by [`encode_versioned`], running it through the chain of
+/// migrations needed to reach the current on-disk version.
+pub fn decode_versioned<T: Migrate>(record: &[u8]) -> crate::Result<T> {
+    let (&[major, minor], bytes) = record.split_first_chunk::<2>().ok_or_else(|| {
+        HqError::GenericError("monitoring event record is too short to contain a version tag".into())
+    })?;
+    let from_version = u16::from_le_bytes([major, minor]);
+    if from_version > T::VERSION {
+        return Err(HqError::GenericError(format!(
+            "monitoring event record was written by a newer HyperQueue (journal version {from_version}, \
+             this binary only understands up to {})",
+            T::VERSION
+        )));
+    }
+    T::migrate(bytes, from_version)
+}
+
+/// Encodes `value` prefixed with its current version tag, for later upgrade by
+/// [`decode_versioned`].
+pub fn encode_versioned<T: Migrate + Serialize>(value: &T) -> crate::Result<Vec<u8>> {
+    let mut buffer = T::VERSION.to_le_bytes().to_vec();
+    bincode::serialize_into(&mut buffer, value)
+        .map_err(|e| HqError::GenericError(format!("failed to serialize monitoring event: {e}")))?;
+    Ok(buffer)
+}
+
+/// Implements [`Migrate`] for a type that has never changed its encoding: any stored
+/// version must equal `VERSION`, and migration is just a plain deserialization.
+macro_rules! impl_migrate_stable {
+    ($ty:ty, $version:expr) => {
+        impl Migrate for $ty {
+            const VERSION: u16 = $version;
+
+            fn migrate(bytes: &[u8], from_version: u16) -> crate::Result<Self> {
+                if from_version != Self::VERSION {
+                    return Err(HqError::GenericError(format!(
+                        "no migration path from monitoring event version {from_version} to {}",
+                        Self::VERSION
+                    )));
+                }
+                bincode::deserialize(bytes)
+                    .map_err(|e| HqError::GenericError(format!("failed to deserialize monitoring event: {e}")))
+            }
+        }
+    };
+}
+
+pub(crate) use impl_migrate_stable;
@@ -0,0 +1,68 @@
+use crate::internal::common::resources::descriptor::GenericResourceDescriptorKind;
+use crate::internal::common::resources::GenericResourceIndex;
+
+/// The concrete units of one generic resource that the scheduler granted to a task, out
+/// of everything the worker advertised in its `GenericResourceDescriptorKind`.
+#[derive(Debug, Clone)]
+pub struct GenericResourceAllocation {
+    pub resource_name: String,
+    pub indices: Vec<GenericResourceIndex>,
+}
+
+impl GenericResourceAllocation {
+    pub fn new(resource_name: String, indices: Vec<GenericResourceIndex>) -> Self {
+        GenericResourceAllocation {
+            resource_name,
+            indices,
+        }
+    }
+
+    /// The environment variable a task should read to find out which units of this
+    /// resource it was granted, e.g. `HQ_RESOURCE_VALUES_gpus`.
+    pub fn env_var_name(&self) -> String {
+        format!("HQ_RESOURCE_VALUES_{}", self.resource_name)
+    }
+
+    /// Renders the allocated indices as the value of [`Self::env_var_name`]: for `Named`
+    /// resources, each index's own label (e.g. a GPU UUID), so the task's environment is
+    /// populated with the exact identifiers it was granted rather than anonymous indices;
+    /// any other kind falls back to the index itself. `kind` must be the descriptor this
+    /// allocation was carved out of.
+    pub fn env_value(&self, kind: &GenericResourceDescriptorKind) -> String {
+        self.indices
+            .iter()
+            .map(|index| {
+                kind.label(*index)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| index.as_num().to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::common::resources::descriptor::GenericResourceDescriptor;
+
+    #[test]
+    fn test_named_allocation_env_value_uses_labels() {
+        let descriptor = GenericResourceDescriptor::named(
+            "gpus",
+            vec!["GPU-0".to_string(), "GPU-1".to_string(), "GPU-2".to_string()],
+        );
+        let allocation =
+            GenericResourceAllocation::new("gpus".to_string(), vec![0.into(), 2.into()]);
+        assert_eq!(allocation.env_var_name(), "HQ_RESOURCE_VALUES_gpus");
+        assert_eq!(allocation.env_value(&descriptor.kind), "GPU-0,GPU-2");
+    }
+
+    #[test]
+    fn test_non_named_allocation_env_value_falls_back_to_indices() {
+        let descriptor = GenericResourceDescriptor::range("mem", 0, 3);
+        let allocation =
+            GenericResourceAllocation::new("mem".to_string(), vec![1.into(), 2.into()]);
+        assert_eq!(allocation.env_value(&descriptor.kind), "1,2");
+    }
+}
@@ -13,7 +13,9 @@ pub enum GenericResourceDescriptorKind {
         start: GenericResourceIndex,
         end: GenericResourceIndex,
     },
-    // TODO: Named(Vec<String>),
+    /// Each unit is identified by a user-supplied label (e.g. a GPU UUID or NIC name)
+    /// instead of an anonymous index, so a scheduler can target a specific unit by identity.
+    Named(Vec<String>),
     Sum {
         size: GenericResourceAmount,
     },
@@ -27,9 +29,24 @@ impl GenericResourceDescriptorKind {
                 (end.as_num() + 1 - start.as_num()) as u64
             }
             GenericResourceDescriptorKind::Range { .. } => 0,
+            GenericResourceDescriptorKind::Named(values) => values.len() as GenericResourceAmount,
             GenericResourceDescriptorKind::Sum { size } => *size,
         }
     }
+
+    /// For `Named` resources, returns the user-supplied label of an allocated unit, so
+    /// that a task's environment can be populated with the exact identifier (e.g. a GPU
+    /// UUID) it was granted rather than an anonymous index. Used by
+    /// [`super::allocation::GenericResourceAllocation::env_value`] to build the value of
+    /// a task's `HQ_RESOURCE_VALUES_*` environment variable.
+    pub fn label(&self, index: GenericResourceIndex) -> Option<&str> {
+        match self {
+            GenericResourceDescriptorKind::Named(values) => {
+                values.get(index.as_num() as usize).map(String::as_str)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for GenericResourceDescriptorKind {
@@ -49,6 +66,9 @@ impl std::fmt::Display for GenericResourceDescriptorKind {
             GenericResourceDescriptorKind::Range { start, end } => {
                 write!(f, "Range({start}-{end})")
             }
+            GenericResourceDescriptorKind::Named(values) => {
+                write!(f, "Named({})", values.join(","))
+            }
             GenericResourceDescriptorKind::Sum { size } => write!(f, "Sum({size})"),
         }
     }
@@ -76,6 +96,12 @@ impl GenericResourceDescriptor {
             kind: GenericResourceDescriptorKind::Sum { size },
         }
     }
+    pub fn named(name: &str, labels: Vec<String>) -> Self {
+        GenericResourceDescriptor {
+            name: name.to_string(),
+            kind: GenericResourceDescriptorKind::Named(labels),
+        }
+    }
 }
 
 /// (Node0(Cpu0, Cpu1), Node1(Cpu2, Cpu3), ...)
@@ -147,6 +173,18 @@ impl ResourceDescriptor {
                 "Same resource defined twice".into(),
             ));
         }
+
+        for generic in &self.generic {
+            if let GenericResourceDescriptorKind::Named(values) = &generic.kind {
+                let labels: Set<&String> = values.iter().collect();
+                if labels.len() != values.len() {
+                    return Err(crate::Error::GenericError(format!(
+                        "Resource '{}' has a duplicate label in its 'Named' units",
+                        generic.name
+                    )));
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -176,4 +214,26 @@ mod tests {
         );
         assert_eq!(&d.full_describe(), "[0, 1, 2, 4], [10, 11, 12, 14]");
     }
+
+    #[test]
+    fn test_named_resource_size_and_display() {
+        let d = GenericResourceDescriptor::named(
+            "gpus",
+            vec!["GPU-0".to_string(), "GPU-1".to_string()],
+        );
+        assert_eq!(d.kind.size(), 2);
+        assert_eq!(d.kind.to_string(), "Named(GPU-0,GPU-1)");
+    }
+
+    #[test]
+    fn test_named_resource_rejects_duplicate_labels() {
+        let d = ResourceDescriptor::new(
+            cpu_descriptor_from_socket_size(1, 1),
+            vec![GenericResourceDescriptor::named(
+                "gpus",
+                vec!["GPU-0".to_string(), "GPU-0".to_string()],
+            )],
+        );
+        assert!(d.validate().is_err());
+    }
 }